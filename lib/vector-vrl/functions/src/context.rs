@@ -4,35 +4,388 @@ use vrl::prelude::*;
 
 use std::collections::{BTreeMap, HashMap};
 use std::collections::hash_map::DefaultHasher;
-use std::sync::Mutex;
-use std::time::Instant;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant, SystemTime};
 use std::hash::Hash;
 use std::hash::Hasher;
-use std::time::Duration;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use base64::Engine as _;
+
+/// Abstracts over time so the context TTL can be driven by a fake, manually
+/// advanced clock in tests instead of depending on wall-clock `Instant::now()`.
+trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// How often the background sweeper walks the backend evicting expired entries.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30);
 
 fn open_context(
 ) -> Resolved {
     Ok(Value::Object(Default::default()))
 }
 
+/// Storage for `SingleContext`s, keyed by the hash of their `keys`. `GlobalContext`
+/// delegates all storage to one of these instead of owning the map itself, so
+/// context state can live in memory (the default) or be made durable across restarts.
+pub trait ContextBackend: Send {
+    fn load(&mut self, hash: u64) -> Option<SingleContext>;
+    fn store(&mut self, hash: u64, context: SingleContext);
+    fn remove(&mut self, hash: u64);
+    /// All hashes the backend currently holds, used by the background sweeper
+    /// to find expired entries without the caller having to know them up front.
+    fn known_hashes(&self) -> Vec<u64>;
+}
+
+/// The original behavior: contexts live only as long as the process does.
+#[derive(Default)]
+pub struct InMemoryBackend {
+    contexts: HashMap<u64, SingleContext>,
+}
+
+impl ContextBackend for InMemoryBackend {
+    fn load(&mut self, hash: u64) -> Option<SingleContext> {
+        self.contexts.get(&hash).map(SingleContext::clone)
+    }
+
+    fn store(&mut self, hash: u64, context: SingleContext) {
+        self.contexts.insert(hash, context);
+    }
+
+    fn remove(&mut self, hash: u64) {
+        self.contexts.remove(&hash);
+    }
+
+    fn known_hashes(&self) -> Vec<u64> {
+        self.contexts.keys().copied().collect()
+    }
+}
+
+/// A context as it's written to disk: `Instant` is process-local and can't be
+/// persisted, so the TTL is tracked as a wall-clock deadline instead. `data` is
+/// stored as `serde_json::Value` rather than `vrl::value::Value` because the
+/// latter doesn't implement `Deserialize` (its `Bytes`/`Regex`/`Timestamp`
+/// variants aren't round-trippable through serde derive), so it's converted
+/// through JSON via `value_to_json`/`json_to_value` on the way in and out.
+#[derive(Serialize, Deserialize)]
+struct PersistedContext {
+    data: serde_json::Value,
+    expires_at: SystemTime,
+    parent: Option<u64>,
+}
+
+/// Converts a VRL `Value` into the JSON it's persisted as. Timestamps and
+/// non-UTF8 byte strings are tagged so `json_to_value` can tell them apart
+/// from plain strings on the way back in.
+fn value_to_json(value: Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Boolean(b) => serde_json::Value::Bool(b),
+        Value::Integer(i) => serde_json::Value::from(i),
+        Value::Float(f) => serde_json::Value::from(f.into_inner()),
+        // The common case (a VRL string) round-trips as a plain JSON string.
+        // Bytes that aren't valid UTF-8 would otherwise be silently corrupted
+        // by `from_utf8_lossy`, so those are base64-tagged instead.
+        Value::Bytes(bytes) => match String::from_utf8(bytes.to_vec()) {
+            Ok(s) => serde_json::Value::String(s),
+            Err(err) => {
+                let mut tagged = serde_json::Map::new();
+                tagged.insert(
+                    "__vrl_bytes_b64__".to_string(),
+                    serde_json::Value::String(
+                        base64::engine::general_purpose::STANDARD.encode(err.into_bytes()),
+                    ),
+                );
+                serde_json::Value::Object(tagged)
+            }
+        },
+        Value::Timestamp(ts) => {
+            let mut tagged = serde_json::Map::new();
+            tagged.insert(
+                "__vrl_timestamp__".to_string(),
+                serde_json::Value::String(ts.to_rfc3339()),
+            );
+            serde_json::Value::Object(tagged)
+        }
+        Value::Array(values) => {
+            serde_json::Value::Array(values.into_iter().map(value_to_json).collect())
+        }
+        Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k.to_string(), value_to_json(v)))
+                .collect(),
+        ),
+        // Regex (and any future variant added to `Value`) has no JSON
+        // representation at all. Tag it explicitly as unrepresentable rather
+        // than silently reinterpreting its debug string as real data on the
+        // next load: `json_to_value` turns this back into `Value::Null`, so
+        // the loss is visible in the reloaded data instead of masquerading as
+        // a string nobody actually stored.
+        other => {
+            let mut tagged = serde_json::Map::new();
+            tagged.insert(
+                "__vrl_unrepresentable__".to_string(),
+                serde_json::Value::String(format!("{other:?}")),
+            );
+            serde_json::Value::Object(tagged)
+        }
+    }
+}
+
+/// The inverse of `value_to_json`.
+fn json_to_value(json: serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Boolean(b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::from(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::from(s),
+        serde_json::Value::Array(values) => {
+            Value::Array(values.into_iter().map(json_to_value).collect())
+        }
+        serde_json::Value::Object(obj) if obj.len() == 1 => {
+            if let Some(serde_json::Value::String(rfc3339)) = obj.get("__vrl_timestamp__") {
+                if let Ok(ts) = DateTime::parse_from_rfc3339(rfc3339) {
+                    return Value::Timestamp(ts.with_timezone(&Utc));
+                }
+            }
+            if let Some(serde_json::Value::String(encoded)) = obj.get("__vrl_bytes_b64__") {
+                if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(encoded) {
+                    return Value::Bytes(bytes.into());
+                }
+            }
+            if obj.contains_key("__vrl_unrepresentable__") {
+                return Value::Null;
+            }
+
+            Value::Object(
+                obj.into_iter()
+                    .map(|(k, v)| (k.into(), json_to_value(v)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Object(obj) => Value::Object(
+            obj.into_iter()
+                .map(|(k, v)| (k.into(), json_to_value(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Durable backend: each context is one CBOR file, so state survives a Vector restart.
+pub struct DiskBackend {
+    dir: PathBuf,
+}
+
+impl DiskBackend {
+    pub fn new(dir: PathBuf) -> std::io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path_for(&self, hash: u64) -> PathBuf {
+        self.dir.join(format!("{hash}.cbor"))
+    }
+}
+
+impl ContextBackend for DiskBackend {
+    fn load(&mut self, hash: u64) -> Option<SingleContext> {
+        let bytes = fs::read(self.path_for(hash)).ok()?;
+        let persisted: PersistedContext = serde_cbor::from_slice(&bytes).ok()?;
+        let remaining = persisted
+            .expires_at
+            .duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO);
+
+        Some(SingleContext {
+            data: json_to_value(persisted.data),
+            until: Instant::now() + remaining,
+            timeout: remaining,
+            parent: persisted.parent,
+        })
+    }
+
+    fn store(&mut self, hash: u64, context: SingleContext) {
+        let remaining = context.until.saturating_duration_since(Instant::now());
+        let persisted = PersistedContext {
+            data: value_to_json(context.data),
+            expires_at: SystemTime::now() + remaining,
+            parent: context.parent,
+        };
+
+        // Flush on every update: a context that's lost between the write syscall
+        // and the next restart defeats the point of a durable backend.
+        if let Ok(bytes) = serde_cbor::to_vec(&persisted) {
+            let _ = fs::write(self.path_for(hash), bytes);
+        }
+    }
+
+    fn remove(&mut self, hash: u64) {
+        let _ = fs::remove_file(self.path_for(hash));
+    }
+
+    fn known_hashes(&self) -> Vec<u64> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.path().file_stem()?.to_str()?.parse().ok())
+            .collect()
+    }
+}
+
 struct GlobalContext {
-    contexts: HashMap<u64, SingleContext>
+    backend: Box<dyn ContextBackend>,
+    clock: Box<dyn Clock>,
 }
 
 impl GlobalContext {
     fn new() -> Self {
-        Self {
-            contexts: HashMap::new(),
+        Self::with_backend_and_clock(Box::new(InMemoryBackend::default()), Box::new(SystemClock))
+    }
+
+    fn with_backend_and_clock(backend: Box<dyn ContextBackend>, clock: Box<dyn Clock>) -> Self {
+        Self { backend, clock }
+    }
+
+    /// Returns the entry for `hash` if it's still live, evicting it from the
+    /// backend first if it's already past its `until` (lazy expiry).
+    fn get_live(&mut self, hash: u64) -> Option<SingleContext> {
+        let entry = self.backend.load(hash)?;
+        if self.clock.now() >= entry.until {
+            self.backend.remove(hash);
+            return None;
+        }
+        Some(entry)
+    }
+
+    /// Walks every entry evicting the ones that have passed their `until`,
+    /// so long-idle keys don't leak memory even if nothing ever looks them up again.
+    fn sweep(&mut self) {
+        let now = self.clock.now();
+        for hash in self.backend.known_hashes() {
+            if matches!(self.backend.load(hash), Some(entry) if now >= entry.until) {
+                self.backend.remove(hash);
+            }
         }
     }
 }
 
-struct SingleContext {
-    data: Value,
-    until: Instant,
+#[derive(Clone)]
+pub struct SingleContext {
+    pub data: Value,
+    pub until: Instant,
+    pub timeout: Duration,
+    /// The hash of the parent context this one inherits data from, if it was
+    /// created via `open_child_context`.
+    pub parent: Option<u64>,
 }
 
 static GLOBAL_CONTEXT: Mutex<Option<GlobalContext>> = Mutex::new(None);
+static SWEEPER_STARTED: Once = Once::new();
+
+/// Spawns the background eviction sweeper exactly once per process.
+fn start_sweeper() {
+    SWEEPER_STARTED.call_once(|| {
+        thread::spawn(|| loop {
+            thread::sleep(SWEEP_INTERVAL);
+            if let Some(global) = GLOBAL_CONTEXT.lock().unwrap().as_mut() {
+                global.sweep();
+            }
+        });
+    });
+}
+
+fn with_global_context<T>(f: impl FnOnce(&mut GlobalContext) -> T) -> T {
+    let mut global = GLOBAL_CONTEXT.lock().unwrap();
+    if global.is_none() {
+        *global = Some(GlobalContext::new());
+    }
+    start_sweeper();
+    f(global.as_mut().unwrap())
+}
+
+/// Selects the backend contexts are stored in. Intended to be called once
+/// during VRL runtime init, before any `open_context`/`update_context` call
+/// runs; calling it later simply discards whatever was held in memory so far.
+pub fn init_context_backend(backend: Box<dyn ContextBackend>) {
+    let mut global = GLOBAL_CONTEXT.lock().unwrap();
+    *global = Some(GlobalContext::with_backend_and_clock(
+        backend,
+        Box::new(SystemClock),
+    ));
+}
+
+/// Deep-merges `incoming` onto `base`: objects merge key-by-key, arrays concatenate,
+/// and anything else is simply replaced by the incoming value.
+fn deep_merge(base: Value, incoming: Value) -> Value {
+    match (base, incoming) {
+        (Value::Object(mut base), Value::Object(incoming)) => {
+            for (key, incoming_value) in incoming {
+                let merged = match base.remove(&key) {
+                    Some(existing_value) => deep_merge(existing_value, incoming_value),
+                    None => incoming_value,
+                };
+                base.insert(key, merged);
+            }
+            Value::Object(base)
+        }
+        (Value::Array(mut base), Value::Array(incoming)) => {
+            base.extend(incoming);
+            Value::Array(base)
+        }
+        (_, incoming) => incoming,
+    }
+}
+
+/// Shared lookup-or-create used by both `open_context` and `open_child_context`:
+/// returns the existing live entry's data, or creates a fresh entry and returns it.
+///
+/// A fresh child (`parent` is `Some`) has its parent's current data snapshotted
+/// into it right away, rather than resolving the parent chain on every read.
+/// That way the child's own entry is self-contained from the moment it's
+/// created: the parent expiring later can't orphan a read of the child mid-way
+/// through, since the child no longer looks at the parent at all.
+fn open_or_create(global: &mut GlobalContext, hash: u64, timeout: Duration, parent: Option<u64>) -> Value {
+    if let Some(existing) = global.get_live(hash) {
+        return existing.data;
+    }
+
+    let inherited = match parent {
+        Some(parent_hash) => global
+            .get_live(parent_hash)
+            .map(|entry| entry.data)
+            .unwrap_or_else(|| Value::Object(ObjectMap::default())),
+        None => Value::Object(ObjectMap::default()),
+    };
+
+    let now = global.clock.now();
+    global.backend.store(hash, SingleContext {
+        data: inherited.clone(),
+        until: now + timeout,
+        timeout,
+        parent,
+    });
+
+    inherited
+}
 
 #[derive(Clone, Copy, Debug)]
 pub struct OpenContext;
@@ -96,20 +449,113 @@ impl FunctionExpression for OpenContextFn {
         let mut hasher = DefaultHasher::new();
         keys.hash(&mut hasher);
         let hash = hasher.finish();
+        let timeout = Duration::from_secs(self.timeout.try_into().unwrap());
+
+        let data = with_global_context(|global| open_or_create(global, hash, timeout, None));
+
+        let mut ret = ObjectMap::new();
+        ret.insert("key".into(), Value::Integer(hash as i64));
+        ret.insert("data".into(), data);
+
+        Ok(Value::Object(ret))
+    }
 
-        let mut global = GLOBAL_CONTEXT.lock().unwrap();
-        if global.is_none() {
-            *global = Some(GlobalContext::new())
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        TypeDef::object(Collection::any())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct OpenChildContext;
+impl Function for OpenChildContext {
+    fn identifier(&self) -> &'static str {
+        "open_child_context"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "parent_context",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "keys",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "seconds",
+                kind: kind::INTEGER,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "open child context",
+            // The child's key is a hash of the parent's key and the child's keys (see
+            // `OpenChildContextFn::resolve`); the example checks only the (inherited)
+            // data, not that key.
+            source: r#"open_child_context(open_context(["session"], 300), ["request"], 5).data"#,
+            result: Ok(r#"{}"#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let parent_context = arguments.required_expr("parent_context");
+        let keys = arguments.required_array("keys")?;
+        let timeout_val = arguments.required_literal("seconds", state)?;
+        let Value::Integer(timeout) = timeout_val else {
+            panic!("Timeout must be integer");
+        };
+
+        Ok(OpenChildContextFn {
+            parent_context,
+            keys,
+            timeout,
         }
-        let global = global.as_mut().unwrap();
-        let entry = global.contexts.entry(hash).or_insert_with(|| SingleContext {
-            data: Value::Object(ObjectMap::default()),
-            until: Instant::now() + Duration::from_secs(self.timeout.try_into().unwrap()),
-        });
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OpenChildContextFn {
+    parent_context: Expr,
+    keys: Vec<Expr>,
+    timeout: i64,
+}
+
+impl FunctionExpression for OpenChildContextFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let Value::Object(parent_context) = self.parent_context.resolve(ctx)? else {
+            panic!("Expected parent_context to be an object");
+        };
+        let Value::Integer(parent_key) = parent_context.get("key").unwrap() else {
+            panic!("Expected parent_context key to be integer");
+        };
+        let parent_hash = *parent_key as u64;
+
+        let keys = self.keys.iter().map(|e| e.resolve(ctx)).collect::<Result<Vec<Value>, ExpressionError>>()?;
+        let mut hasher = DefaultHasher::new();
+        // Hash the parent in too, so the same child keys opened under different
+        // parents land on distinct entries instead of colliding.
+        parent_hash.hash(&mut hasher);
+        keys.hash(&mut hasher);
+        let hash = hasher.finish();
+        let timeout = Duration::from_secs(self.timeout.try_into().unwrap());
+
+        let data = with_global_context(|global| open_or_create(global, hash, timeout, Some(parent_hash)));
 
         let mut ret = ObjectMap::new();
         ret.insert("key".into(), Value::Integer(hash as i64));
-        ret.insert("data".into(), entry.data.clone());
+        ret.insert("data".into(), data);
 
         Ok(Value::Object(ret))
     }
@@ -140,7 +586,9 @@ impl Function for UpdateContext {
     fn examples(&self) -> &'static [Example] {
         &[Example {
             title: "update context",
-            source: r#"update_context({"key": 8194875, "data": { "hi": 5 }})"#,
+            // Opens the key itself rather than assuming some other example's
+            // `open_context` already ran first in the same process.
+            source: r#"ctx = open_context(["test"], 5); update_context({"key": ctx.key, "data": { "hi": 5 }})"#,
             result: Ok(r#"null"#),
         }]
     }
@@ -173,16 +621,33 @@ impl FunctionExpression for UpdateContextFn {
         let Value::Integer(key_value) = context.get("key").unwrap() else {
             panic!("Expected key to be integer");
         };
+        let hash = *key_value as u64;
+        let data = context.get("data").unwrap().clone();
 
-        let mut global = GLOBAL_CONTEXT.lock().unwrap();
-        if global.is_none() {
-            *global = Some(GlobalContext::new())
-        }
-        let global = global.as_mut().unwrap();
-        global.contexts.insert(*key_value as u64, SingleContext {
-            data: context.get("data").unwrap().clone(),
-            until: Instant::now(),
-        });
+        with_global_context(|global| -> Result<(), ExpressionError> {
+            match global.get_live(hash) {
+                // The context is still live: keep its `until` as-is. Resetting it to
+                // `now()` here is what used to make the TTL meaningless.
+                Some(existing) => {
+                    global.backend.store(hash, SingleContext {
+                        data,
+                        until: existing.until,
+                        timeout: existing.timeout,
+                        parent: existing.parent,
+                    });
+                    Ok(())
+                }
+                // No live entry to update (it either never existed or just expired):
+                // there's no timeout left to honor, so storing it anyway would just
+                // create a dead entry the next lookup silently evicts, discarding
+                // `data`. Erroring instead tells the caller their key is gone rather
+                // than pretending the update succeeded.
+                None => Err(format!(
+                    "no live context for key `{key_value}`; call open_context first"
+                )
+                .into()),
+            }
+        })?;
 
         Ok(Value::Null)
     }
@@ -191,3 +656,528 @@ impl FunctionExpression for UpdateContextFn {
         TypeDef::null()
     }
 }
+
+#[derive(Clone, Copy, Debug)]
+pub struct AccumulateContext;
+impl Function for AccumulateContext {
+    fn identifier(&self) -> &'static str {
+        "accumulate_context"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "keys",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "seconds",
+                kind: kind::INTEGER,
+                required: true,
+            },
+            Parameter {
+                keyword: "merge",
+                kind: kind::OBJECT,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "accumulate context",
+            source: r#"accumulate_context(["test"], 5, { "count": 1 })"#,
+            result: Ok(r#"{"count": 1}"#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let keys = arguments.required_array("keys")?;
+        let timeout_val = arguments.required_literal("seconds", state)?;
+        let Value::Integer(timeout) = timeout_val else {
+            panic!("Timeout must be integer");
+        };
+        let merge = arguments.required_expr("merge");
+
+        Ok(AccumulateContextFn {
+            keys,
+            timeout,
+            merge,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccumulateContextFn {
+    keys: Vec<Expr>,
+    timeout: i64,
+    merge: Expr,
+}
+
+impl FunctionExpression for AccumulateContextFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let keys = self.keys.iter().map(|e| e.resolve(ctx)).collect::<Result<Vec<Value>, ExpressionError>>()?;
+        let mut hasher = DefaultHasher::new();
+        keys.hash(&mut hasher);
+        let hash = hasher.finish();
+        let timeout = Duration::from_secs(self.timeout.try_into().unwrap());
+        let merge = self.merge.resolve(ctx)?;
+
+        // The whole lookup-or-create, merge, and store happens under a single lock
+        // acquisition, unlike the open_context/update_context pair, which releases
+        // the lock between the read and the write and can lose concurrent updates.
+        let merged = with_global_context(|global| {
+            let (base, until, entry_timeout, parent) = match global.get_live(hash) {
+                Some(existing) => (existing.data, existing.until, existing.timeout, existing.parent),
+                None => {
+                    let now = global.clock.now();
+                    (Value::Object(ObjectMap::default()), now + timeout, timeout, None)
+                }
+            };
+
+            let merged = deep_merge(base, merge);
+            global.backend.store(hash, SingleContext {
+                data: merged.clone(),
+                until,
+                timeout: entry_timeout,
+                parent,
+            });
+            merged
+        });
+
+        Ok(merged)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        TypeDef::object(Collection::any())
+    }
+}
+
+/// The coercion to apply when reading a field back out of a context, parsed once
+/// from the `type` argument string at compile time rather than re-parsed per event.
+#[derive(Debug, Clone, PartialEq)]
+enum FieldConversion {
+    Integer,
+    Float,
+    Boolean,
+    Bytes,
+    Timestamp,
+    TimestampFmt(String),
+}
+
+impl FieldConversion {
+    fn parse(input: &str) -> Result<Self, String> {
+        match input {
+            "integer" => Ok(Self::Integer),
+            "float" => Ok(Self::Float),
+            "boolean" => Ok(Self::Boolean),
+            "bytes" | "string" => Ok(Self::Bytes),
+            "timestamp" => Ok(Self::Timestamp),
+            other => other
+                .strip_prefix("timestamp_fmt:")
+                .map(|fmt| Self::TimestampFmt(fmt.to_owned()))
+                .ok_or_else(|| format!("unknown context field conversion `{other}`")),
+        }
+    }
+
+    fn convert(&self, value: Value) -> Resolved {
+        match self {
+            Self::Integer => match value {
+                Value::Integer(_) => Ok(value),
+                Value::Float(f) => Ok(Value::Integer(f.into_inner() as i64)),
+                Value::Bytes(ref bytes) => std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|s| s.trim().parse::<i64>().ok())
+                    .map(Value::Integer)
+                    .ok_or_else(|| "could not convert context field to integer".into()),
+                _ => Err("could not convert context field to integer".into()),
+            },
+            Self::Float => match value {
+                Value::Float(_) => Ok(value),
+                Value::Integer(i) => Ok(Value::from(i as f64)),
+                Value::Bytes(ref bytes) => std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|s| s.trim().parse::<f64>().ok())
+                    .map(Value::from)
+                    .ok_or_else(|| "could not convert context field to float".into()),
+                _ => Err("could not convert context field to float".into()),
+            },
+            Self::Boolean => match value {
+                Value::Boolean(_) => Ok(value),
+                Value::Bytes(ref bytes) => match std::str::from_utf8(bytes) {
+                    Ok("true") => Ok(Value::Boolean(true)),
+                    Ok("false") => Ok(Value::Boolean(false)),
+                    _ => Err("could not convert context field to boolean".into()),
+                },
+                _ => Err("could not convert context field to boolean".into()),
+            },
+            // Strings pass through untouched; this is also where raw bytes end up.
+            Self::Bytes => Ok(value),
+            Self::Timestamp => Self::parse_timestamp(&value, None),
+            Self::TimestampFmt(fmt) => Self::parse_timestamp(&value, Some(fmt)),
+        }
+    }
+
+    fn parse_timestamp(value: &Value, fmt: Option<&str>) -> Resolved {
+        // A field stashed via `update_context`/`accumulate_context`, or reloaded
+        // from a `DiskBackend` (which reconstructs `Value::Timestamp` in
+        // `json_to_value`), may already be a timestamp; pass it through like the
+        // `Integer`/`Float`/`Boolean` arms do for their already-correct-typed value.
+        if let Value::Timestamp(_) = value {
+            return Ok(value.clone());
+        }
+        let Value::Bytes(bytes) = value else {
+            return Err("context field must be a string to parse as a timestamp".into());
+        };
+        let input = String::from_utf8_lossy(bytes);
+
+        let parsed = match fmt {
+            None => DateTime::parse_from_rfc3339(&input)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|err| format!("could not parse `{input}` as an RFC3339 timestamp: {err}"))?,
+            // A custom format may only specify a date (e.g. `%Y-%m-%d`) or only a
+            // time (e.g. `%H:%M:%S`), in which case a full `NaiveDateTime` parse
+            // always fails even though the format is otherwise valid. Fall back to
+            // `NaiveDate` (assumed midnight) and then `NaiveTime` (combined with the
+            // Unix epoch date) before giving up.
+            Some(fmt) => chrono::NaiveDateTime::parse_from_str(&input, fmt)
+                .map(|naive| DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc))
+                .or_else(|_| {
+                    chrono::NaiveDate::parse_from_str(&input, fmt).map(|date| {
+                        DateTime::<Utc>::from_naive_utc_and_offset(date.and_hms_opt(0, 0, 0).unwrap(), Utc)
+                    })
+                })
+                .or_else(|_| {
+                    chrono::NaiveTime::parse_from_str(&input, fmt).map(|time| {
+                        DateTime::<Utc>::from_naive_utc_and_offset(
+                            chrono::NaiveDate::from_ymd_opt(1970, 1, 1)
+                                .unwrap()
+                                .and_time(time),
+                            Utc,
+                        )
+                    })
+                })
+                .map_err(|err| format!("could not parse `{input}` with format `{fmt}`: {err}"))?,
+        };
+
+        Ok(Value::Timestamp(parsed))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GetContextField;
+impl Function for GetContextField {
+    fn identifier(&self) -> &'static str {
+        "get_context_field"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "context",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "path",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "type",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "get context field",
+            source: r#"get_context_field(accumulate_context(["test"], 5, {"count": 1}), ["count"], "integer")"#,
+            result: Ok("1"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        state: &TypeState,
+        ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let context = arguments.required_expr("context");
+        let path = arguments.required_array("path")?;
+        let type_val = arguments.required_literal("type", state)?;
+        let Value::Bytes(type_bytes) = type_val else {
+            panic!("type must be a string");
+        };
+        let conversion = FieldConversion::parse(&String::from_utf8_lossy(&type_bytes))
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        Ok(GetContextFieldFn {
+            context,
+            path,
+            conversion,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GetContextFieldFn {
+    context: Expr,
+    path: Vec<Expr>,
+    conversion: FieldConversion,
+}
+
+impl FunctionExpression for GetContextFieldFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let context = self.context.resolve(ctx)?;
+        let path = self.path.iter().map(|e| e.resolve(ctx)).collect::<Result<Vec<Value>, ExpressionError>>()?;
+
+        // `context` is either a context object (`{"key": ..., "data": ...}`, as
+        // returned by `open_context`) or the `data` object itself (as returned by
+        // `accumulate_context`); either way, that's where the path is read from.
+        let root = match &context {
+            Value::Object(map) => map.get("data").cloned().unwrap_or(context),
+            _ => context,
+        };
+
+        let mut current = root;
+        for segment in &path {
+            let Value::Bytes(key_bytes) = segment else {
+                return Err("context field path segments must be strings".into());
+            };
+            let key = String::from_utf8_lossy(key_bytes).into_owned();
+
+            current = match current {
+                Value::Object(map) => map
+                    .get(key.as_str())
+                    .cloned()
+                    .ok_or_else(|| format!("no field `{key}` in context data"))?,
+                _ => return Err(format!("cannot look up field `{key}` on a non-object value").into()),
+            };
+        }
+
+        self.conversion.convert(current)
+    }
+
+    fn type_def(&self, _: &TypeState) -> TypeDef {
+        TypeDef::any().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Clone)]
+    struct FakeClock {
+        base: Instant,
+        offset_secs: Arc<AtomicU64>,
+    }
+
+    impl FakeClock {
+        fn new() -> Self {
+            Self {
+                base: Instant::now(),
+                offset_secs: Arc::new(AtomicU64::new(0)),
+            }
+        }
+
+        fn advance(&self, secs: u64) {
+            self.offset_secs.fetch_add(secs, Ordering::SeqCst);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.base + Duration::from_secs(self.offset_secs.load(Ordering::SeqCst))
+        }
+    }
+
+    fn in_memory(clock: FakeClock) -> GlobalContext {
+        GlobalContext::with_backend_and_clock(Box::new(InMemoryBackend::default()), Box::new(clock))
+    }
+
+    #[test]
+    fn expire_evicts_only_once_the_clock_passes_until() {
+        let clock = FakeClock::new();
+        let mut global = in_memory(clock.clone());
+        let hash = 1;
+        global.backend.store(hash, SingleContext {
+            data: Value::Object(ObjectMap::default()),
+            until: clock.now() + Duration::from_secs(5),
+            timeout: Duration::from_secs(5),
+            parent: None,
+        });
+
+        assert!(global.get_live(hash).is_some());
+
+        clock.advance(10);
+        assert!(global.get_live(hash).is_none());
+        assert!(global.backend.load(hash).is_none());
+    }
+
+    #[test]
+    fn sweep_evicts_every_expired_entry_regardless_of_lookups() {
+        let clock = FakeClock::new();
+        let mut global = in_memory(clock.clone());
+        global.backend.store(1, SingleContext {
+            data: Value::Object(ObjectMap::default()),
+            until: clock.now() + Duration::from_secs(5),
+            timeout: Duration::from_secs(5),
+            parent: None,
+        });
+        global.backend.store(2, SingleContext {
+            data: Value::Object(ObjectMap::default()),
+            until: clock.now() + Duration::from_secs(20),
+            timeout: Duration::from_secs(20),
+            parent: None,
+        });
+
+        clock.advance(10);
+        global.sweep();
+
+        assert!(global.backend.load(1).is_none());
+        assert!(global.backend.load(2).is_some());
+    }
+
+    #[test]
+    fn disk_backend_round_trips_through_a_wall_clock_expiry() {
+        let dir = std::env::temp_dir().join(format!("vector-context-test-{:?}", std::thread::current().id()));
+        let mut backend = DiskBackend::new(dir.clone()).unwrap();
+
+        backend.store(1, SingleContext {
+            data: Value::Integer(42),
+            until: Instant::now() + Duration::from_secs(60),
+            timeout: Duration::from_secs(60),
+            parent: None,
+        });
+
+        let loaded = backend.load(1).expect("entry should survive a reload from disk");
+        assert_eq!(loaded.data, Value::Integer(42));
+        assert!(loaded.until > Instant::now());
+
+        backend.remove(1);
+        assert!(backend.load(1).is_none());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn deep_merge_recurses_into_objects_concatenates_arrays_and_replaces_scalars() {
+        let base = Value::from(BTreeMap::from([
+            ("count".into(), Value::Integer(1)),
+            ("tags".into(), Value::Array(vec![Value::from("a")])),
+            (
+                "nested".into(),
+                Value::from(BTreeMap::from([("a".into(), Value::Integer(1))])),
+            ),
+        ]));
+        let incoming = Value::from(BTreeMap::from([
+            ("count".into(), Value::Integer(2)),
+            ("tags".into(), Value::Array(vec![Value::from("b")])),
+            (
+                "nested".into(),
+                Value::from(BTreeMap::from([("b".into(), Value::Integer(2))])),
+            ),
+        ]));
+
+        let Value::Object(merged) = deep_merge(base, incoming) else {
+            panic!("expected merge of two objects to produce an object");
+        };
+
+        assert_eq!(merged.get("count"), Some(&Value::Integer(2)));
+        assert_eq!(
+            merged.get("tags"),
+            Some(&Value::Array(vec![Value::from("a"), Value::from("b")]))
+        );
+        assert_eq!(
+            merged.get("nested"),
+            Some(&Value::from(BTreeMap::from([
+                ("a".into(), Value::Integer(1)),
+                ("b".into(), Value::Integer(2)),
+            ])))
+        );
+    }
+
+    #[test]
+    fn field_conversion_parses_known_specs_and_rejects_unknown_ones() {
+        assert_eq!(FieldConversion::parse("integer"), Ok(FieldConversion::Integer));
+        assert_eq!(FieldConversion::parse("bytes"), Ok(FieldConversion::Bytes));
+        assert_eq!(FieldConversion::parse("string"), Ok(FieldConversion::Bytes));
+        assert_eq!(
+            FieldConversion::parse("timestamp_fmt:%Y-%m-%d"),
+            Ok(FieldConversion::TimestampFmt("%Y-%m-%d".to_owned()))
+        );
+        assert!(FieldConversion::parse("nonsense").is_err());
+    }
+
+    #[test]
+    fn field_conversion_coerces_a_stored_string_into_an_integer() {
+        let converted = FieldConversion::Integer
+            .convert(Value::from("42"))
+            .expect("numeric string should convert");
+        assert_eq!(converted, Value::Integer(42));
+    }
+
+    #[test]
+    fn field_conversion_fails_on_an_unparseable_value() {
+        assert!(FieldConversion::Integer.convert(Value::from("not a number")).is_err());
+    }
+
+    #[test]
+    fn open_or_create_snapshots_the_parents_data_into_a_new_child() {
+        let clock = FakeClock::new();
+        let mut global = in_memory(clock);
+        global.backend.store(1, SingleContext {
+            data: Value::from(BTreeMap::from([("session_id".into(), Value::from("abc"))])),
+            until: Instant::now() + Duration::from_secs(60),
+            timeout: Duration::from_secs(60),
+            parent: None,
+        });
+
+        let child_data = open_or_create(&mut global, 2, Duration::from_secs(5), Some(1));
+
+        let Value::Object(child) = child_data else {
+            panic!("expected an object");
+        };
+        assert_eq!(child.get("session_id"), Some(&Value::from("abc")));
+    }
+
+    #[test]
+    fn child_context_read_survives_the_parent_being_evicted() {
+        let clock = FakeClock::new();
+        let mut global = in_memory(clock.clone());
+        global.backend.store(1, SingleContext {
+            data: Value::from(BTreeMap::from([("session_id".into(), Value::from("abc"))])),
+            until: clock.now() + Duration::from_secs(5),
+            timeout: Duration::from_secs(5),
+            parent: None,
+        });
+
+        // Creating the child snapshots the parent's data into it right away, so
+        // the child holds its own independent copy rather than a live reference.
+        open_or_create(&mut global, 2, Duration::from_secs(60), Some(1));
+
+        // The parent expires shortly after, but the child is unaffected: it's no
+        // longer looked up through the parent at all.
+        clock.advance(10);
+        assert!(global.get_live(1).is_none());
+
+        let Value::Object(child) = global.get_live(2).expect("child should still be live").data else {
+            panic!("expected an object");
+        };
+        assert_eq!(child.get("session_id"), Some(&Value::from("abc")));
+    }
+}